@@ -0,0 +1,244 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Lit, Meta, NestedMeta, Path, Type};
+
+/// Derives a `from_env()` constructor that builds `Self` by reading each field from an
+/// environment variable via `envoption::require`/`with_default`/`optional`.
+///
+/// The variable name defaults to the field name, uppercased; override it with
+/// `#[env(name = "...")]`. A field typed `Option<T>` is read with `envoption::optional`; a field
+/// annotated `#[env(default = "...")]` is read with `envoption::with_default`; every other field
+/// is read with `envoption::require`. Every field is read before `from_env()` returns, so a
+/// missing or unparseable value is reported alongside any others rather than stopping at the
+/// first one.
+#[proc_macro_derive(EnvConfig, attributes(env))]
+pub fn derive_env_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => return syn::Error::new_spanned(&name, "EnvConfig can only be derived for structs with named fields").to_compile_error().into(),
+        },
+        _ => return syn::Error::new_spanned(&name, "EnvConfig can only be derived for structs").to_compile_error().into(),
+    };
+
+    let mut errors: Vec<syn::Error> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut field_reads = Vec::new();
+    let mut field_names = Vec::new();
+
+    for field in fields.iter() {
+        let field_name = field.ident.as_ref().expect("named field");
+        let field_type = &field.ty;
+
+        let mut var_name = field_name.to_string().to_uppercase();
+        let mut default_value: Option<String> = None;
+
+        for attr in &field.attrs {
+            if !attr.path.is_ident("env") {
+                continue;
+            }
+
+            let list = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                Ok(other) => {
+                    errors.push(syn::Error::new_spanned(other, "expected #[env(name = \"...\")] and/or #[env(default = \"...\")]"));
+                    continue;
+                }
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            for item in list.nested {
+                match item {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => match nv.lit {
+                        Lit::Str(s) => var_name = s.value(),
+                        other => errors.push(syn::Error::new_spanned(other, "#[env(name = \"...\")] expects a string literal")),
+                    },
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("default") => match nv.lit {
+                        Lit::Str(s) => default_value = Some(s.value()),
+                        other => errors.push(syn::Error::new_spanned(other, "#[env(default = \"...\")] expects a string literal")),
+                    },
+                    other => errors.push(syn::Error::new_spanned(other, "unrecognized #[env(...)] item, expected `name = \"...\"` or `default = \"...\"`")),
+                }
+            }
+        }
+
+        let inner_type = option_inner_type(field_type);
+
+        if inner_type.is_some() && default_value.is_some() {
+            // Redundant, not wrong: warn rather than hard-error, by emitting a call to a
+            // `#[deprecated]` function, which the compiler warns about without refusing to build.
+            let warn_fn = format_ident!("__envoption_redundant_option_default_{}", field_name, span = field_name.span());
+            let trigger_fn = format_ident!("__envoption_redundant_option_default_{}_warn", field_name, span = field_name.span());
+            warnings.push(quote_spanned! {field_name.span()=>
+                #[deprecated(note = "#[env(default = \"...\")] has no effect on an Option<T> field, which is already optional")]
+                #[allow(non_snake_case)]
+                fn #warn_fn() {}
+                #[allow(dead_code, non_snake_case)]
+                fn #trigger_fn() { #warn_fn(); }
+            });
+        }
+
+        let read = if let Some(inner) = inner_type {
+            quote! {
+                match envoption::optional::<#inner>(#var_name) {
+                    Ok(v) => Some(v),
+                    Err(e) => { errors.push(e.into()); None }
+                }
+            }
+        } else if let Some(default_value) = default_value {
+            quote! {
+                match #default_value.parse::<#field_type>() {
+                    Ok(default) => match envoption::with_default::<#field_type, #field_type>(#var_name, default) {
+                        Ok(v) => Some(v),
+                        Err(e) => { errors.push(e.into()); None }
+                    },
+                    Err(e) => {
+                        errors.push(envoption::BatchError::ParseError(#var_name.to_string(), Box::new(e)));
+                        None
+                    }
+                }
+            }
+        } else {
+            quote! {
+                match envoption::require::<#field_type>(#var_name) {
+                    Ok(v) => Some(v),
+                    Err(e) => { errors.push(e.into()); None }
+                }
+            }
+        };
+
+        field_reads.push(quote! { let #field_name = #read; });
+        field_names.push(field_name);
+    }
+
+    if let Some(error) = combine_errors(errors) {
+        return error.to_compile_error().into();
+    }
+
+    let expanded = quote! {
+        #(#warnings)*
+
+        impl #name {
+            /// Build this config struct by reading each field from its environment variable,
+            /// collecting every missing or unparseable field into the returned `Vec` instead of
+            /// stopping at the first one (see `envoption::EnvBatch`).
+            pub fn from_env() -> Result<Self, Vec<envoption::BatchError>> {
+                let mut errors: Vec<envoption::BatchError> = Vec::new();
+
+                #(#field_reads)*
+
+                if !errors.is_empty() {
+                    return Err(errors);
+                }
+
+                Ok(#name {
+                    #(#field_names: #field_names.unwrap()),*
+                })
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+fn combine_errors(errors: Vec<syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().fold(None, |acc, e| {
+        Some(match acc {
+            Some(mut combined) => {
+                combined.combine(e);
+                combined
+            }
+            None => e,
+        })
+    })
+}
+
+/// Returns the inner type of `ty` if it is `Option<T>` (matched as `Option`,
+/// `std::option::Option`, or `core::option::Option`), otherwise `None`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => &type_path.path,
+        _ => return None,
+    };
+
+    if !is_option_path(path) {
+        return None;
+    }
+
+    let segment = path.segments.last()?;
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(t) => Some(t),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn is_option_path(path: &Path) -> bool {
+    let idents: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let segments: Vec<&str> = idents.iter().map(String::as_str).collect();
+    matches!(
+        segments.as_slice(),
+        ["Option"] | ["std", "option", "Option"] | ["core", "option", "Option"]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    mod is_option_path {
+        use super::*;
+
+        #[test]
+        fn bare_option() {
+            let path: Path = parse_quote!(Option);
+            assert!(is_option_path(&path));
+        }
+
+        #[test]
+        fn std_qualified() {
+            let path: Path = parse_quote!(std::option::Option);
+            assert!(is_option_path(&path));
+        }
+
+        #[test]
+        fn core_qualified() {
+            let path: Path = parse_quote!(core::option::Option);
+            assert!(is_option_path(&path));
+        }
+
+        #[test]
+        fn unrelated_type() {
+            let path: Path = parse_quote!(String);
+            assert!(!is_option_path(&path));
+        }
+    }
+
+    mod option_inner_type {
+        use super::*;
+
+        #[test]
+        fn extracts_inner_type() {
+            let ty: Type = parse_quote!(Option<u16>);
+            let inner = option_inner_type(&ty).expect("should be Option<T>");
+            assert_eq!(quote!(#inner).to_string(), quote!(u16).to_string());
+        }
+
+        #[test]
+        fn non_option_returns_none() {
+            let ty: Type = parse_quote!(String);
+            assert!(option_inner_type(&ty).is_none());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::env;
+
+use envoption_derive::EnvConfig;
+
+#[derive(EnvConfig, Debug, PartialEq)]
+struct HappyPathConfig {
+    #[env(name = "__ENVOPTION_DERIVE_TEST_HAPPY_HOST")]
+    host: String,
+    #[env(name = "__ENVOPTION_DERIVE_TEST_HAPPY_PORT", default = "8080")]
+    port: u16,
+    #[env(name = "__ENVOPTION_DERIVE_TEST_HAPPY_NICKNAME")]
+    nickname: Option<String>,
+}
+
+#[test]
+fn from_env_happy_path() {
+    env::set_var("__ENVOPTION_DERIVE_TEST_HAPPY_HOST", "localhost");
+    env::remove_var("__ENVOPTION_DERIVE_TEST_HAPPY_PORT");
+    env::remove_var("__ENVOPTION_DERIVE_TEST_HAPPY_NICKNAME");
+
+    let config = HappyPathConfig::from_env().unwrap();
+    assert_eq!(
+        config,
+        HappyPathConfig { host: String::from("localhost"), port: 8080, nickname: None }
+    );
+}
+
+#[derive(EnvConfig, Debug, PartialEq)]
+struct ErrorCollectingConfig {
+    #[env(name = "__ENVOPTION_DERIVE_TEST_ERRORS_HOST")]
+    host: String,
+    #[env(name = "__ENVOPTION_DERIVE_TEST_ERRORS_PORT", default = "8080")]
+    port: u16,
+}
+
+#[test]
+fn from_env_collects_every_error() {
+    env::remove_var("__ENVOPTION_DERIVE_TEST_ERRORS_HOST");
+    env::set_var("__ENVOPTION_DERIVE_TEST_ERRORS_PORT", "not-a-port");
+
+    let errors = ErrorCollectingConfig::from_env().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(format!("{}", errors[0]), "__ENVOPTION_DERIVE_TEST_ERRORS_HOST not found");
+    assert_eq!(
+        format!("{}", errors[1]),
+        "parsing __ENVOPTION_DERIVE_TEST_ERRORS_PORT: invalid digit found in string"
+    );
+}
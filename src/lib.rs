@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::env;
+use std::hash::Hash;
 use std::str::FromStr;
+use std::str::ParseBoolError;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Debug;
@@ -14,6 +17,39 @@ pub enum OptionType<T> {
     Default(T),
 }
 
+/// Message and description text shared between `EnvOptionError` and `BatchError`, which expose
+/// the same set of variants but can't share an enum because `BatchError` type-erases the parse
+/// error. Keeping the text here means a new variant only has to be worded once.
+mod error_text {
+    use std::fmt;
+
+    pub fn fmt_parse_error(f: &mut fmt::Formatter, var: &str, err: &fmt::Display) -> fmt::Result {
+        write!(f, "parsing {}: {}", var, err)
+    }
+
+    pub fn fmt_missing(f: &mut fmt::Formatter, var: &str) -> fmt::Result {
+        write!(f, "{} not found", var)
+    }
+
+    pub fn fmt_conflicting_vars(f: &mut fmt::Formatter, a: &str, b: &str) -> fmt::Result {
+        write!(f, "{} conflicts with {}: only one may be set", a, b)
+    }
+
+    pub fn fmt_conditionally_required(f: &mut fmt::Formatter, trigger: &str, missing: &str) -> fmt::Result {
+        write!(f, "{} is required because {} is set", missing, trigger)
+    }
+
+    pub fn fmt_not_allowed(f: &mut fmt::Formatter, name: &str, got: &str, allowed: &[String]) -> fmt::Result {
+        write!(f, "{}={} is not one of: {}", name, got, allowed.join(", "))
+    }
+
+    pub const DESC_PARSE_ERROR: &str = "parse error";
+    pub const DESC_MISSING: &str = "variable is required";
+    pub const DESC_CONFLICTING_VARS: &str = "conflicting variables";
+    pub const DESC_CONDITIONALLY_REQUIRED: &str = "conditionally required variable is missing";
+    pub const DESC_NOT_ALLOWED: &str = "value is not one of the allowed values";
+}
+
 #[derive(Debug,PartialEq)]
 pub enum EnvOptionError<T> where T: Error {
     /// An error occurred while parsing the environment variable.
@@ -21,13 +57,24 @@ pub enum EnvOptionError<T> where T: Error {
     ParseError(String, T),
     /// The environment variable was missing.
     Missing(String),
+    /// Two variables that must not both be set (see `Requirements::conflicts_with`) were both present.
+    ConflictingVars(String, String),
+    /// `trigger` was present (see `Requirements::requires`/`required_if`), which made `missing` required, but it wasn't set.
+    ConditionallyRequired { trigger: String, missing: String },
+    /// The value (see `get_one_of`), either parsed from the environment variable or the default
+    /// used when it is unset, wasn't one of the allowed values. Carries the variable name, the
+    /// rejected value, and the list of permitted values.
+    NotAllowed(String, String, Vec<String>),
 }
 
 impl<T> fmt::Display for EnvOptionError<T> where T: Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            EnvOptionError::ParseError(ref s, ref err) => write!(f, "parsing {}: {}", s, err),
-            EnvOptionError::Missing(ref s) => write!(f, "{} not found", s),
+            EnvOptionError::ParseError(ref s, ref err) => error_text::fmt_parse_error(f, s, err),
+            EnvOptionError::Missing(ref s) => error_text::fmt_missing(f, s),
+            EnvOptionError::ConflictingVars(ref a, ref b) => error_text::fmt_conflicting_vars(f, a, b),
+            EnvOptionError::ConditionallyRequired { ref trigger, ref missing } => error_text::fmt_conditionally_required(f, trigger, missing),
+            EnvOptionError::NotAllowed(ref name, ref got, ref allowed) => error_text::fmt_not_allowed(f, name, got, allowed),
         }
     }
 }
@@ -35,19 +82,43 @@ impl<T> fmt::Display for EnvOptionError<T> where T: Error {
 impl<T> Error for EnvOptionError<T> where T: Error {
     fn description(&self) -> &str {
         match *self {
-            EnvOptionError::ParseError(_, _) => "parse error",
-            EnvOptionError::Missing(_) => "variable is required"
+            EnvOptionError::ParseError(_, _) => error_text::DESC_PARSE_ERROR,
+            EnvOptionError::Missing(_) => error_text::DESC_MISSING,
+            EnvOptionError::ConflictingVars(_, _) => error_text::DESC_CONFLICTING_VARS,
+            EnvOptionError::ConditionallyRequired { .. } => error_text::DESC_CONDITIONALLY_REQUIRED,
+            EnvOptionError::NotAllowed(_, _, _) => error_text::DESC_NOT_ALLOWED,
         }
     }
 
     fn cause(&self) -> Option<&Error> {
         match *self {
             EnvOptionError::ParseError(_, ref e) => Some(e),
-            EnvOptionError::Missing(_) => None
+            EnvOptionError::Missing(_) => None,
+            EnvOptionError::ConflictingVars(_, _) => None,
+            EnvOptionError::ConditionallyRequired { .. } => None,
+            EnvOptionError::NotAllowed(_, _, _) => None,
         }
     }
 }
 
+/// A placeholder `FromStr::Err` type for `EnvOptionError` instances that can only ever be one of
+/// the non-parsing variants (`ConflictingVars`/`ConditionallyRequired`), such as those returned
+/// by `Requirements::check`, which never parses a variable's value.
+#[derive(Debug, PartialEq)]
+pub struct NeverParsed;
+
+impl fmt::Display for NeverParsed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(no parse error)")
+    }
+}
+
+impl Error for NeverParsed {
+    fn description(&self) -> &str {
+        "no parse error"
+    }
+}
+
 /// Get an environment variable, using the given mode to determine behavior when it is not set.
 pub fn get<T,B>(var_name: &str, mode: OptionType<B>) -> Result<Option<T>, EnvOptionError<T::Err>>  where B: Into<T>, T : FromStr + Debug, T::Err: Error {
     match env::var(var_name) {
@@ -73,6 +144,373 @@ pub fn optional<T>(var_name: &str) -> Result<Option<T>, EnvOptionError<T::Err>>
     get::<T,T>(var_name, OptionType::Optional)
 }
 
+/// Split the environment variable on `delimiter`, trim each piece, and `parse` it into a `T`,
+/// using the given mode to determine behavior when the variable is not set.
+///
+/// An empty value yields an empty `Vec` rather than a single empty element. If one of the
+/// pieces fails to parse, the error reports its index, e.g. `parsing HOSTS[2]: invalid digit`.
+pub fn get_vec<T>(var_name: &str, delimiter: &str, mode: OptionType<Vec<T>>) -> Result<Option<Vec<T>>, EnvOptionError<T::Err>> where T: FromStr + Debug, T::Err: Error {
+    match env::var(var_name) {
+        Err(_) => match mode {
+            OptionType::Optional => Ok(None),
+            OptionType::Required => Err(EnvOptionError::Missing(var_name.to_string())),
+            OptionType::Default(d) => Ok(Some(d)),
+        },
+        Ok(value) => {
+            if value.is_empty() {
+                return Ok(Some(Vec::new()));
+            }
+
+            value.split(delimiter)
+                .enumerate()
+                .map(|(i, piece)| piece.trim().parse::<T>().map_err(|e| EnvOptionError::ParseError(format!("{}[{}]", var_name, i), e)))
+                .collect::<Result<Vec<T>, _>>()
+                .map(Some)
+        },
+    }
+}
+
+/// Sugar around `get_vec` to avoid the extra `Option` when it will never be `None` anyway.
+pub fn require_vec<T>(var_name: &str, delimiter: &str) -> Result<Vec<T>, EnvOptionError<T::Err>> where T: FromStr + Debug, T::Err: Error {
+    get_vec(var_name, delimiter, OptionType::Required).map(|o| o.unwrap())
+}
+
+pub fn with_default_vec<T>(var_name: &str, delimiter: &str, default: Vec<T>) -> Result<Vec<T>, EnvOptionError<T::Err>> where T: FromStr + Debug, T::Err: Error {
+    get_vec(var_name, delimiter, OptionType::Default(default)).map(|o| o.unwrap())
+}
+
+pub fn optional_vec<T>(var_name: &str, delimiter: &str) -> Result<Option<Vec<T>>, EnvOptionError<T::Err>> where T: FromStr + Debug, T::Err: Error {
+    get_vec(var_name, delimiter, OptionType::Optional)
+}
+
+/// An error from parsing one side of a `key_delimiter`-separated pair in `get_map`, or from a
+/// pair that has no `key_delimiter` at all. This exists so that `get_map` can report a single
+/// error type regardless of whether the key or the value failed to parse.
+#[derive(Debug,PartialEq)]
+pub struct MapEntryError(String);
+
+impl fmt::Display for MapEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for MapEntryError {
+    fn description(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Split the environment variable on `pair_delimiter` into `key_delimiter`-separated pairs and
+/// `parse` each side into a `HashMap<K, V>`, using the given mode to determine behavior when the
+/// variable is not set. For example, `get_map::<String, i32>("WEIGHTS", ";", "=", ...)` parses
+/// `A=1;B=2` into `{"A": 1, "B": 2}`.
+pub fn get_map<K, V>(var_name: &str, pair_delimiter: &str, key_delimiter: &str, mode: OptionType<HashMap<K, V>>) -> Result<Option<HashMap<K, V>>, EnvOptionError<MapEntryError>>
+    where K: FromStr + Eq + Hash, K::Err: Error, V: FromStr, V::Err: Error {
+    match env::var(var_name) {
+        Err(_) => match mode {
+            OptionType::Optional => Ok(None),
+            OptionType::Required => Err(EnvOptionError::Missing(var_name.to_string())),
+            OptionType::Default(d) => Ok(Some(d)),
+        },
+        Ok(value) => {
+            if value.is_empty() {
+                return Ok(Some(HashMap::new()));
+            }
+
+            value.split(pair_delimiter)
+                .enumerate()
+                .map(|(i, pair)| {
+                    let mut parts = pair.splitn(2, key_delimiter);
+                    let key = parts.next().unwrap_or("");
+                    let value = parts.next().ok_or_else(|| EnvOptionError::ParseError(format!("{}[{}]", var_name, i), MapEntryError(format!("missing `{}` between key and value", key_delimiter))))?;
+
+                    let key = key.trim().parse::<K>().map_err(|e| EnvOptionError::ParseError(format!("{}[{}]", var_name, i), MapEntryError(e.to_string())))?;
+                    let value = value.trim().parse::<V>().map_err(|e| EnvOptionError::ParseError(format!("{}[{}]", var_name, i), MapEntryError(e.to_string())))?;
+                    Ok((key, value))
+                })
+                .collect::<Result<HashMap<K, V>, _>>()
+                .map(Some)
+        },
+    }
+}
+
+/// Sugar around `get_map` to avoid the extra `Option` when it will never be `None` anyway.
+pub fn require_map<K, V>(var_name: &str, pair_delimiter: &str, key_delimiter: &str) -> Result<HashMap<K, V>, EnvOptionError<MapEntryError>>
+    where K: FromStr + Eq + Hash, K::Err: Error, V: FromStr, V::Err: Error {
+    get_map(var_name, pair_delimiter, key_delimiter, OptionType::Required).map(|o| o.unwrap())
+}
+
+pub fn with_default_map<K, V>(var_name: &str, pair_delimiter: &str, key_delimiter: &str, default: HashMap<K, V>) -> Result<HashMap<K, V>, EnvOptionError<MapEntryError>>
+    where K: FromStr + Eq + Hash, K::Err: Error, V: FromStr, V::Err: Error {
+    get_map(var_name, pair_delimiter, key_delimiter, OptionType::Default(default)).map(|o| o.unwrap())
+}
+
+pub fn optional_map<K, V>(var_name: &str, pair_delimiter: &str, key_delimiter: &str) -> Result<Option<HashMap<K, V>>, EnvOptionError<MapEntryError>>
+    where K: FromStr + Eq + Hash, K::Err: Error, V: FromStr, V::Err: Error {
+    get_map(var_name, pair_delimiter, key_delimiter, OptionType::Optional)
+}
+
+const TRUTHY_VALUES: &[&str] = &["true", "yes", "on", "1", "enabled"];
+const FALSY_VALUES: &[&str] = &["false", "no", "off", "0", "disabled"];
+
+/// Case-insensitively parse a wider vocabulary of boolean-ish strings than `bool::from_str`
+/// accepts. Anything outside `TRUTHY_VALUES`/`FALSY_VALUES` is reported via a real
+/// `ParseBoolError`, obtained from the standard library itself so its `Display` stays accurate.
+fn parse_bool_loose(value: &str) -> Result<bool, ParseBoolError> {
+    let lower = value.to_lowercase();
+    if TRUTHY_VALUES.contains(&lower.as_str()) {
+        Ok(true)
+    } else if FALSY_VALUES.contains(&lower.as_str()) {
+        Ok(false)
+    } else {
+        Err(value.parse::<bool>().unwrap_err())
+    }
+}
+
+/// Get an environment variable as a `bool`, accepting a wider vocabulary than Rust's strict
+/// `true`/`false` (e.g. `yes`, `on`, `1`, `enabled` and their opposites), using the given mode
+/// to determine behavior when it is not set.
+pub fn get_bool(var_name: &str, mode: OptionType<bool>) -> Result<Option<bool>, EnvOptionError<ParseBoolError>> {
+    match env::var(var_name) {
+        Err(_) => match mode {
+            OptionType::Optional => Ok(None),
+            OptionType::Required => Err(EnvOptionError::Missing(var_name.to_string())),
+            OptionType::Default(d) => Ok(Some(d)),
+        },
+        Ok(value) => parse_bool_loose(&value).map(Some).map_err(|e| EnvOptionError::ParseError(var_name.to_string(), e)),
+    }
+}
+
+/// Sugar around `get_bool` to avoid the extra `Option` when it will never be `None` anyway.
+pub fn require_bool(var_name: &str) -> Result<bool, EnvOptionError<ParseBoolError>> {
+    get_bool(var_name, OptionType::Required).map(|o| o.unwrap())
+}
+
+pub fn with_default_bool(var_name: &str, default: bool) -> Result<bool, EnvOptionError<ParseBoolError>> {
+    get_bool(var_name, OptionType::Default(default)).map(|o| o.unwrap())
+}
+
+pub fn optional_bool(var_name: &str) -> Result<Option<bool>, EnvOptionError<ParseBoolError>> {
+    get_bool(var_name, OptionType::Optional)
+}
+
+/// Get an environment variable, using the given mode to determine behavior when it is not set,
+/// and additionally require that the parsed value (or the default, when it is used) appear in
+/// `allowed`, e.g. `get_one_of::<String>("LOG_LEVEL", &levels, OptionType::Required)` where
+/// `levels` is a `Vec<String>` of `trace`, `debug`, `info`, `warn` and `error`.
+pub fn get_one_of<T>(var_name: &str, allowed: &[T], mode: OptionType<T>) -> Result<Option<T>, EnvOptionError<T::Err>>
+    where T: FromStr + Debug + PartialEq + ToString, T::Err: Error {
+    match env::var(var_name) {
+        Err(_) => match mode {
+            OptionType::Optional => Ok(None),
+            OptionType::Required => Err(EnvOptionError::Missing(var_name.to_string())),
+            OptionType::Default(d) => {
+                if allowed.contains(&d) {
+                    Ok(Some(d))
+                } else {
+                    Err(EnvOptionError::NotAllowed(var_name.to_string(), d.to_string(), allowed.iter().map(T::to_string).collect()))
+                }
+            },
+        },
+        Ok(value) => {
+            let parsed = value.parse::<T>().map_err(|e| EnvOptionError::ParseError(var_name.to_string(), e))?;
+            if allowed.contains(&parsed) {
+                Ok(Some(parsed))
+            } else {
+                Err(EnvOptionError::NotAllowed(var_name.to_string(), value, allowed.iter().map(T::to_string).collect()))
+            }
+        },
+    }
+}
+
+/// Sugar around `get_one_of` to avoid the extra `Option` when it will never be `None` anyway.
+pub fn require_one_of<T>(var_name: &str, allowed: &[T]) -> Result<T, EnvOptionError<T::Err>> where T: FromStr + Debug + PartialEq + ToString, T::Err: Error {
+    get_one_of(var_name, allowed, OptionType::Required).map(|o| o.unwrap())
+}
+
+pub fn with_default_one_of<T>(var_name: &str, allowed: &[T], default: T) -> Result<T, EnvOptionError<T::Err>> where T: FromStr + Debug + PartialEq + ToString, T::Err: Error {
+    get_one_of(var_name, allowed, OptionType::Default(default)).map(|o| o.unwrap())
+}
+
+pub fn optional_one_of<T>(var_name: &str, allowed: &[T]) -> Result<Option<T>, EnvOptionError<T::Err>> where T: FromStr + Debug + PartialEq + ToString, T::Err: Error {
+    get_one_of(var_name, allowed, OptionType::Optional)
+}
+
+/// A type-erased error produced while collecting multiple environment variable reads through
+/// `EnvBatch`. `EnvOptionError<T>` is generic over a single `FromStr::Err`, which doesn't let a
+/// batch of heterogeneous reads share one `Vec`, so this flattens the parse error down to a
+/// boxed `dyn Error` and otherwise has the same variants as `EnvOptionError`.
+#[derive(Debug)]
+pub enum BatchError {
+    /// The environment variable was missing.
+    Missing(String),
+    /// The environment variable's value failed to parse.
+    ParseError(String, Box<Error>),
+    /// Two variables that must not both be set were both present.
+    ConflictingVars(String, String),
+    /// `trigger` was present, which made `missing` required, but it wasn't set.
+    ConditionallyRequired { trigger: String, missing: String },
+    /// The environment variable's value, or the default used when it is unset, wasn't one of the allowed values.
+    NotAllowed(String, String, Vec<String>),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BatchError::ParseError(ref s, ref err) => error_text::fmt_parse_error(f, s, err.as_ref()),
+            BatchError::Missing(ref s) => error_text::fmt_missing(f, s),
+            BatchError::ConflictingVars(ref a, ref b) => error_text::fmt_conflicting_vars(f, a, b),
+            BatchError::ConditionallyRequired { ref trigger, ref missing } => error_text::fmt_conditionally_required(f, trigger, missing),
+            BatchError::NotAllowed(ref name, ref got, ref allowed) => error_text::fmt_not_allowed(f, name, got, allowed),
+        }
+    }
+}
+
+impl Error for BatchError {
+    fn description(&self) -> &str {
+        match *self {
+            BatchError::ParseError(_, _) => error_text::DESC_PARSE_ERROR,
+            BatchError::Missing(_) => error_text::DESC_MISSING,
+            BatchError::ConflictingVars(_, _) => error_text::DESC_CONFLICTING_VARS,
+            BatchError::ConditionallyRequired { .. } => error_text::DESC_CONDITIONALLY_REQUIRED,
+            BatchError::NotAllowed(_, _, _) => error_text::DESC_NOT_ALLOWED,
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            BatchError::ParseError(_, ref e) => Some(e.as_ref()),
+            BatchError::Missing(_) => None,
+            BatchError::ConflictingVars(_, _) => None,
+            BatchError::ConditionallyRequired { .. } => None,
+            BatchError::NotAllowed(_, _, _) => None,
+        }
+    }
+}
+
+impl<T> From<EnvOptionError<T>> for BatchError where T: Error + 'static {
+    fn from(err: EnvOptionError<T>) -> BatchError {
+        match err {
+            EnvOptionError::Missing(s) => BatchError::Missing(s),
+            EnvOptionError::ParseError(s, e) => BatchError::ParseError(s, Box::new(e)),
+            EnvOptionError::ConflictingVars(a, b) => BatchError::ConflictingVars(a, b),
+            EnvOptionError::ConditionallyRequired { trigger, missing } => BatchError::ConditionallyRequired { trigger, missing },
+            EnvOptionError::NotAllowed(name, got, allowed) => BatchError::NotAllowed(name, got, allowed),
+        }
+    }
+}
+
+/// Collects the results of several `require`/`with_default`/`optional` reads and reports every
+/// missing or unparseable variable at once instead of stopping at the first problem encountered.
+///
+/// Note that a successful read's value is discarded, not returned from `finish()` — `EnvBatch` is
+/// a validation pass, so a caller that also needs the values must read each variable again (with
+/// `require`/`with_default`/`optional` directly) after `finish()` returns `Ok(())`.
+#[derive(Default)]
+pub struct EnvBatch {
+    errors: Vec<BatchError>,
+}
+
+impl EnvBatch {
+    pub fn new() -> EnvBatch {
+        EnvBatch { errors: Vec::new() }
+    }
+
+    /// Register a required read. Any error is recorded rather than returned immediately.
+    pub fn require<T>(mut self, var_name: &str) -> Self where T: FromStr + Debug, T::Err: Error + 'static {
+        if let Err(e) = require::<T>(var_name) {
+            self.errors.push(e.into());
+        }
+        self
+    }
+
+    /// Register a read with a default value. Any error is recorded rather than returned immediately.
+    pub fn with_default<T, B>(mut self, var_name: &str, default: B) -> Self where B: Into<T>, T: FromStr + Debug, T::Err: Error + 'static {
+        if let Err(e) = with_default(var_name, default) {
+            self.errors.push(e.into());
+        }
+        self
+    }
+
+    /// Register an optional read. Any parse error is recorded rather than returned immediately;
+    /// a missing variable is not an error for an optional read.
+    pub fn optional<T>(mut self, var_name: &str) -> Self where T: FromStr + Debug, T::Err: Error + 'static {
+        if let Err(e) = optional::<T>(var_name) {
+            self.errors.push(e.into());
+        }
+        self
+    }
+
+    /// Finish the batch, returning every error collected along the way.
+    pub fn finish(self) -> Result<(), Vec<BatchError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// Cross-variable requirement and conflict rules on top of the existing getters: real configs
+/// have conditional dependencies (`TLS_ENABLED=true` makes `TLS_CERT_PATH` required;
+/// `USE_SQLITE` and `DATABASE_URL` are mutually exclusive) that a single `get`/`require` call
+/// can't express.
+#[derive(Default)]
+pub struct Requirements {
+    requires: Vec<(String, String)>,
+    conflicts: Vec<(String, String)>,
+    required_if: Vec<(String, String, String)>,
+}
+
+impl Requirements {
+    pub fn new() -> Requirements {
+        Requirements { requires: Vec::new(), conflicts: Vec::new(), required_if: Vec::new() }
+    }
+
+    /// If `trigger` is set, `required` must also be set.
+    pub fn requires(mut self, trigger: &str, required: &str) -> Self {
+        self.requires.push((trigger.to_string(), required.to_string()));
+        self
+    }
+
+    /// `a` and `b` must not both be set.
+    pub fn conflicts_with(mut self, a: &str, b: &str) -> Self {
+        self.conflicts.push((a.to_string(), b.to_string()));
+        self
+    }
+
+    /// If `trigger` is set to exactly `expected_value`, `required` must also be set.
+    pub fn required_if(mut self, trigger: &str, expected_value: &str, required: &str) -> Self {
+        self.required_if.push((trigger.to_string(), expected_value.to_string(), required.to_string()));
+        self
+    }
+
+    /// Check every registered rule against the current environment, returning the first
+    /// violation encountered.
+    pub fn check(&self) -> Result<(), EnvOptionError<NeverParsed>> {
+        for (trigger, required) in &self.requires {
+            if env::var(trigger).is_ok() && env::var(required).is_err() {
+                return Err(EnvOptionError::ConditionallyRequired { trigger: trigger.clone(), missing: required.clone() });
+            }
+        }
+
+        for (a, b) in &self.conflicts {
+            if env::var(a).is_ok() && env::var(b).is_ok() {
+                return Err(EnvOptionError::ConflictingVars(a.clone(), b.clone()));
+            }
+        }
+
+        for (trigger, expected_value, required) in &self.required_if {
+            if env::var(trigger).map(|v| v == *expected_value).unwrap_or(false) && env::var(required).is_err() {
+                return Err(EnvOptionError::ConditionallyRequired { trigger: trigger.clone(), missing: required.clone() });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     pub use super::*;
@@ -85,6 +523,10 @@ mod tests {
         env::set_var(SET_OPTION, "10");
     }
 
+    pub fn levels() -> Vec<String> {
+        vec!["trace", "debug", "info", "warn", "error"].into_iter().map(String::from).collect()
+    }
+
     mod get {
         pub use super::*;
 
@@ -249,4 +691,356 @@ mod tests {
         }
     }
 
+    mod get_vec {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(get_vec::<usize>(UNSET_OPTION, ",", OptionType::Optional), Result::Ok(None));
+        }
+
+        #[test]
+        fn var_not_present_required() {
+            assert_eq!(get_vec::<usize>(UNSET_OPTION, ",", OptionType::Required), Result::Err(EnvOptionError::Missing(String::from(UNSET_OPTION))));
+        }
+
+        #[test]
+        fn var_not_present_default() {
+            assert_eq!(get_vec(UNSET_OPTION, ",", OptionType::Default(vec![1, 2])), Result::Ok(Some(vec![1, 2])));
+        }
+
+        #[test]
+        fn var_is_present() {
+            const VAR : &'static str = "__ENVOPTION_TEST_LIST_VAR_IS_PRESENT";
+            env::set_var(VAR, "1, 2,3");
+            assert_eq!(get_vec::<usize>(VAR, ",", OptionType::Required), Result::Ok(Some(vec![1, 2, 3])));
+        }
+
+        #[test]
+        fn empty_value_yields_empty_vec() {
+            const VAR : &'static str = "__ENVOPTION_TEST_LIST_EMPTY_VALUE";
+            env::set_var(VAR, "");
+            assert_eq!(get_vec::<usize>(VAR, ",", OptionType::Required), Result::Ok(Some(Vec::new())));
+        }
+
+        #[test]
+        fn parse_error_reports_index() {
+            const VAR : &'static str = "__ENVOPTION_TEST_LIST_PARSE_ERROR";
+            env::set_var(VAR, "1,two,3");
+            let err = get_vec::<usize>(VAR, ",", OptionType::Required).unwrap_err();
+            assert_eq!(format!("{}", err), format!("parsing {}[1]: invalid digit found in string", VAR));
+        }
+    }
+
+    mod require_vec {
+        pub use super::*;
+
+        #[test]
+        fn var_is_present() {
+            env::set_var("__ENVOPTION_TEST_REQUIRE_VEC", "a:b:c");
+            assert_eq!(require_vec::<String>("__ENVOPTION_TEST_REQUIRE_VEC", ":"), Result::Ok(vec![String::from("a"), String::from("b"), String::from("c")]));
+        }
+    }
+
+    mod with_default_vec {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(with_default_vec(UNSET_OPTION, ",", vec![String::from("x")]), Result::Ok(vec![String::from("x")]));
+        }
+    }
+
+    mod optional_vec {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(optional_vec::<usize>(UNSET_OPTION, ","), Result::Ok(None));
+        }
+    }
+
+    mod get_map {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(get_map::<String, usize>(UNSET_OPTION, ";", "=", OptionType::Optional), Result::Ok(None));
+        }
+
+        #[test]
+        fn var_is_present() {
+            const VAR : &'static str = "__ENVOPTION_TEST_MAP_VAR_IS_PRESENT";
+            env::set_var(VAR, "A=1;B=2");
+            let mut expected = HashMap::new();
+            expected.insert(String::from("A"), 1);
+            expected.insert(String::from("B"), 2);
+            assert_eq!(get_map::<String, usize>(VAR, ";", "=", OptionType::Required), Result::Ok(Some(expected)));
+        }
+
+        #[test]
+        fn empty_value_yields_empty_map() {
+            const VAR : &'static str = "__ENVOPTION_TEST_MAP_EMPTY_VALUE";
+            env::set_var(VAR, "");
+            assert_eq!(get_map::<String, usize>(VAR, ";", "=", OptionType::Required), Result::Ok(Some(HashMap::new())));
+        }
+
+        #[test]
+        fn parse_error_reports_index() {
+            const VAR : &'static str = "__ENVOPTION_TEST_MAP_PARSE_ERROR";
+            env::set_var(VAR, "A=1;B=two");
+            let err = get_map::<String, usize>(VAR, ";", "=", OptionType::Required).unwrap_err();
+            assert_eq!(format!("{}", err), format!("parsing {}[1]: invalid digit found in string", VAR));
+        }
+    }
+
+    mod get_bool {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(get_bool(UNSET_OPTION, OptionType::Optional), Result::Ok(None));
+        }
+
+        #[test]
+        fn var_not_present_default() {
+            assert_eq!(get_bool(UNSET_OPTION, OptionType::Default(true)), Result::Ok(Some(true)));
+        }
+
+        #[test]
+        fn strict_values() {
+            const VAR : &'static str = "__ENVOPTION_TEST_BOOL_STRICT";
+
+            env::set_var(VAR, "true");
+            assert_eq!(get_bool(VAR, OptionType::Required), Result::Ok(Some(true)));
+
+            env::set_var(VAR, "false");
+            assert_eq!(get_bool(VAR, OptionType::Required), Result::Ok(Some(false)));
+        }
+
+        #[test]
+        fn loose_truthy_values() {
+            const VAR : &'static str = "__ENVOPTION_TEST_BOOL_LOOSE_TRUTHY";
+
+            for value in &["yes", "ON", "1", "Enabled"] {
+                env::set_var(VAR, value);
+                assert_eq!(get_bool(VAR, OptionType::Required), Result::Ok(Some(true)), "value was {}", value);
+            }
+        }
+
+        #[test]
+        fn loose_falsy_values() {
+            const VAR : &'static str = "__ENVOPTION_TEST_BOOL_LOOSE_FALSY";
+
+            for value in &["no", "OFF", "0", "Disabled"] {
+                env::set_var(VAR, value);
+                assert_eq!(get_bool(VAR, OptionType::Required), Result::Ok(Some(false)), "value was {}", value);
+            }
+        }
+
+        #[test]
+        fn unrecognized_value_is_parse_error() {
+            const VAR : &'static str = "__ENVOPTION_TEST_BOOL_UNRECOGNIZED";
+
+            env::set_var(VAR, "maybe");
+            let err = get_bool(VAR, OptionType::Required).unwrap_err();
+            assert_eq!(format!("{}", err), format!("parsing {}: provided string was not `true` or `false`", VAR));
+        }
+    }
+
+    mod require_bool {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(require_bool(UNSET_OPTION), Result::Err(EnvOptionError::Missing(String::from(UNSET_OPTION))));
+        }
+    }
+
+    mod with_default_bool {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(with_default_bool(UNSET_OPTION, true), Result::Ok(true));
+        }
+    }
+
+    mod optional_bool {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(optional_bool(UNSET_OPTION), Result::Ok(None));
+        }
+    }
+
+    mod env_batch {
+        pub use super::*;
+
+        #[test]
+        fn no_errors() {
+            set_env();
+            let result = EnvBatch::new()
+                .require::<String>(SET_OPTION)
+                .with_default::<usize, _>(UNSET_OPTION, 5usize)
+                .optional::<String>(UNSET_OPTION)
+                .finish();
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn collects_every_error() {
+            env::set_var("__ENVOPTION_TEST_BATCH_BAD", "not-a-number");
+            let errors = EnvBatch::new()
+                .require::<usize>(UNSET_OPTION)
+                .require::<usize>("__ENVOPTION_TEST_BATCH_BAD")
+                .finish()
+                .unwrap_err();
+
+            assert_eq!(errors.len(), 2);
+            assert_eq!(format!("{}", errors[0]), format!("{} not found", UNSET_OPTION));
+            assert_eq!(format!("{}", errors[1]), "parsing __ENVOPTION_TEST_BATCH_BAD: invalid digit found in string");
+        }
+
+        #[test]
+        fn optional_missing_var_is_not_an_error() {
+            let result = EnvBatch::new().optional::<String>(UNSET_OPTION).finish();
+            assert!(result.is_ok());
+        }
+    }
+
+    mod requirements {
+        pub use super::*;
+
+        #[test]
+        fn requires_satisfied() {
+            const TRIGGER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_SATISFIED_TRIGGER";
+            const OTHER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_SATISFIED_OTHER";
+            env::set_var(TRIGGER, "1");
+            env::set_var(OTHER, "1");
+            assert_eq!(Requirements::new().requires(TRIGGER, OTHER).check(), Result::Ok(()));
+        }
+
+        #[test]
+        fn requires_violated() {
+            const TRIGGER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_VIOLATED_TRIGGER";
+            const OTHER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_VIOLATED_OTHER";
+            env::remove_var(OTHER);
+            env::set_var(TRIGGER, "1");
+            assert_eq!(
+                Requirements::new().requires(TRIGGER, OTHER).check(),
+                Result::Err(EnvOptionError::ConditionallyRequired { trigger: String::from(TRIGGER), missing: String::from(OTHER) })
+            );
+        }
+
+        #[test]
+        fn requires_not_triggered() {
+            const TRIGGER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_NOT_TRIGGERED_TRIGGER";
+            const OTHER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRES_NOT_TRIGGERED_OTHER";
+            env::remove_var(TRIGGER);
+            assert_eq!(Requirements::new().requires(TRIGGER, OTHER).check(), Result::Ok(()));
+        }
+
+        #[test]
+        fn conflicts_with_violated() {
+            const A : &'static str = "__ENVOPTION_TEST_REQ_CONFLICTS_VIOLATED_A";
+            const B : &'static str = "__ENVOPTION_TEST_REQ_CONFLICTS_VIOLATED_B";
+            env::set_var(A, "1");
+            env::set_var(B, "1");
+            assert_eq!(
+                Requirements::new().conflicts_with(A, B).check(),
+                Result::Err(EnvOptionError::ConflictingVars(String::from(A), String::from(B)))
+            );
+        }
+
+        #[test]
+        fn conflicts_with_satisfied() {
+            const A : &'static str = "__ENVOPTION_TEST_REQ_CONFLICTS_SATISFIED_A";
+            const B : &'static str = "__ENVOPTION_TEST_REQ_CONFLICTS_SATISFIED_B";
+            env::set_var(A, "1");
+            env::remove_var(B);
+            assert_eq!(Requirements::new().conflicts_with(A, B).check(), Result::Ok(()));
+        }
+
+        #[test]
+        fn required_if_matching_value_violated() {
+            const TRIGGER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRED_IF_VIOLATED_TRIGGER";
+            const OTHER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRED_IF_VIOLATED_OTHER";
+            env::remove_var(OTHER);
+            env::set_var(TRIGGER, "true");
+            assert_eq!(
+                Requirements::new().required_if(TRIGGER, "true", OTHER).check(),
+                Result::Err(EnvOptionError::ConditionallyRequired { trigger: String::from(TRIGGER), missing: String::from(OTHER) })
+            );
+        }
+
+        #[test]
+        fn required_if_different_value_is_not_triggered() {
+            const TRIGGER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRED_IF_NOT_TRIGGERED_TRIGGER";
+            const OTHER : &'static str = "__ENVOPTION_TEST_REQ_REQUIRED_IF_NOT_TRIGGERED_OTHER";
+            env::set_var(TRIGGER, "false");
+            assert_eq!(Requirements::new().required_if(TRIGGER, "true", OTHER).check(), Result::Ok(()));
+        }
+    }
+
+    mod get_one_of {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(get_one_of(UNSET_OPTION, &levels(), OptionType::Optional), Result::Ok(None));
+        }
+
+        #[test]
+        fn allowed_value() {
+            const VAR : &'static str = "__ENVOPTION_TEST_LOG_LEVEL_ALLOWED";
+            env::set_var(VAR, "warn");
+            assert_eq!(get_one_of(VAR, &levels(), OptionType::Required), Result::Ok(Some(String::from("warn"))));
+        }
+
+        #[test]
+        fn disallowed_value() {
+            const VAR : &'static str = "__ENVOPTION_TEST_LOG_LEVEL_DISALLOWED";
+            env::set_var(VAR, "trce");
+            let err = get_one_of(VAR, &levels(), OptionType::Required).unwrap_err();
+            assert_eq!(format!("{}", err), format!("{}=trce is not one of: trace, debug, info, warn, error", VAR));
+        }
+    }
+
+    mod require_one_of {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(require_one_of(UNSET_OPTION, &levels()), Result::Err(EnvOptionError::Missing(String::from(UNSET_OPTION))));
+        }
+    }
+
+    mod with_default_one_of {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(with_default_one_of(UNSET_OPTION, &levels(), String::from("info")), Result::Ok(String::from("info")));
+        }
+
+        #[test]
+        fn var_not_present_default_disallowed() {
+            const VAR : &'static str = "__ENVOPTION_TEST_WITH_DEFAULT_ONE_OF_DISALLOWED";
+            env::remove_var(VAR);
+            let err = with_default_one_of(VAR, &levels(), String::from("trce")).unwrap_err();
+            assert_eq!(format!("{}", err), format!("{}=trce is not one of: trace, debug, info, warn, error", VAR));
+        }
+    }
+
+    mod optional_one_of {
+        pub use super::*;
+
+        #[test]
+        fn var_not_present() {
+            assert_eq!(optional_one_of(UNSET_OPTION, &levels()), Result::Ok(None));
+        }
+    }
+
 }